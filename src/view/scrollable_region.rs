@@ -1,18 +1,48 @@
+use std::ops::Range;
 use scribe::buffer::{line_range, LineRange};
 
-/// Abstract representation of a fixed-height section of the screen.
-/// Used to determine visible ranges of lines based on previous state,
-/// explicit line focus, and common scrolling implementation behaviours.
+/// Abstract representation of a fixed-size section of the screen, indexed
+/// by line and column like a terminal grid. Used to determine visible
+/// ranges of lines and columns based on previous state, explicit focus,
+/// and common scrolling implementation behaviours.
 pub struct ScrollableRegion {
     height: usize,
+    width: usize,
     line_offset: usize,
+    column_offset: usize,
+    line_count: Option<usize>,
+    overscroll: usize,
+    scroll_margin: usize,
 }
 
 #[derive(PartialEq, Debug)]
 pub enum Visibility {
     AboveRegion,
-    Visible(usize),
     BelowRegion,
+    LeftOfRegion,
+    RightOfRegion,
+    Visible(usize, usize),
+}
+
+/// Where to place a line when recentering via `scroll_to`, mirroring vi's
+/// `zt` (top), `zz` (center), and `zb` (bottom).
+#[derive(PartialEq, Debug)]
+pub enum ScrollAnchor {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// The outcome of a page or half-page scroll. Reports the region's new
+/// line offset along with the signed number of lines the caller should
+/// move the cursor by so it keeps the same relative screen row. When the
+/// region clamps against a bound (the top, or a known bottom) the offset
+/// stops but the full delta is still reported, letting the caller consume
+/// the residual movement inside the viewport.
+#[derive(PartialEq, Debug)]
+pub struct ScrollResult {
+    pub line_offset: usize,
+    pub cursor_delta: isize,
 }
 
 impl ScrollableRegion {
@@ -20,38 +50,103 @@ impl ScrollableRegion {
     pub fn height(&self) -> usize {
         self.height
     }
-    
+
+    // The width of the scrollable region.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
     // Determines the visible lines based on the current line offset and height.
     pub fn visible_range(&self) -> LineRange {
         line_range::new(self.line_offset, self.height + self.line_offset)
     }
 
+    // Determines the visible columns based on the current column offset and width.
+    pub fn visible_column_range(&self) -> Range<usize> {
+        self.column_offset..(self.column_offset + self.width)
+    }
+
     /// If necessary, moves the line offset such that the specified line is
     /// visible, using previous state to determine whether said line is at
     /// the top or bottom of the new visible range.
     pub fn scroll_into_view(&mut self, line: usize) {
         let range = self.visible_range();
-        if line < range.start() {
-            self.line_offset = line;
-        } else if line >= range.end() {
-            self.line_offset = line - self.height + 1;
+        // A non-zero margin keeps that many lines between the target line
+        // and the edge it would otherwise sit flush against.
+        let margin = self.scroll_margin;
+        if line < range.start() + margin {
+            self.set_line_offset(line.saturating_sub(margin));
+        } else if line + margin >= range.end() {
+            self.set_line_offset((line + margin + 1).saturating_sub(self.height));
         }
     }
 
-    /// Converts an absolutely positioned line number into
-    /// one relative to the scrollable regions visible range.
-    /// The visibility type is based on whether or not the line
-    /// is outside of the region's visible range.
-    pub fn relative_position(&self, line: usize) -> Visibility {
-        match line.checked_sub(self.line_offset) {
-            Some(line) => {
-                if line >= self.height {
-                    Visibility::BelowRegion
-                } else {
-                    Visibility::Visible(line)
-                }
-            },
-            None => Visibility::AboveRegion,
+    /// Sets the scrolloff-style context margin: the minimum number of lines
+    /// kept visible above and below the target of `scroll_into_view` so the
+    /// cursor rarely sits flush against the top or bottom edge. Defaults to
+    /// zero, which preserves the minimal-movement behaviour.
+    pub fn set_scroll_margin(&mut self, scroll_margin: usize) {
+        self.scroll_margin = scroll_margin;
+    }
+
+    /// Forces the specified line to a particular placement within the
+    /// region regardless of the current offset, clamped to valid bounds.
+    /// This backs vi-style `zz`/`zt`/`zb` recentering, in contrast to
+    /// `scroll_into_view`, which only nudges the offset the minimum amount.
+    pub fn scroll_to(&mut self, line: usize, anchor: ScrollAnchor) {
+        let offset = match anchor {
+            ScrollAnchor::Top => line,
+            ScrollAnchor::Center => line.saturating_sub(self.height / 2),
+            ScrollAnchor::Bottom => line.saturating_sub(self.height - 1),
+        };
+        self.set_line_offset(offset);
+    }
+
+    /// Informs the region of the document's line count so that offsets can
+    /// be clamped to the buffer's bounds. Re-sanitizes the current offset.
+    pub fn set_line_count(&mut self, line_count: usize) {
+        self.line_count = Some(line_count);
+        self.set_line_offset(self.line_offset);
+    }
+
+    /// Sets the number of lines the region is permitted to scroll past the
+    /// last clamped offset. Defaults to zero. Re-sanitizes the current
+    /// offset.
+    pub fn set_overscroll(&mut self, overscroll: usize) {
+        self.overscroll = overscroll;
+        self.set_line_offset(self.line_offset);
+    }
+
+    /// If necessary, moves both offsets such that the specified point is
+    /// visible. The line offset is adjusted by `scroll_into_view`, scroll
+    /// margin included; the column offset is nudged the minimum amount,
+    /// with no margin.
+    pub fn scroll_point_into_view(&mut self, line: usize, column: usize) {
+        self.scroll_into_view(line);
+
+        let range = self.visible_column_range();
+        if column < range.start {
+            self.column_offset = column;
+        } else if column >= range.end {
+            self.column_offset = column - self.width + 1;
+        }
+    }
+
+    /// Converts an absolutely positioned point into one relative to the
+    /// scrollable region's visible range. The visibility type reports
+    /// whether the point lies outside the region on any edge, and carries
+    /// a relative `(row, column)` pair when it is visible.
+    pub fn relative_position(&self, line: usize, column: usize) -> Visibility {
+        let row = match line.checked_sub(self.line_offset) {
+            Some(row) if row < self.height => row,
+            Some(_) => return Visibility::BelowRegion,
+            None => return Visibility::AboveRegion,
+        };
+
+        match column.checked_sub(self.column_offset) {
+            Some(column) if column < self.width => Visibility::Visible(row, column),
+            Some(_) => Visibility::RightOfRegion,
+            None => Visibility::LeftOfRegion,
         }
     }
 
@@ -61,33 +156,108 @@ impl ScrollableRegion {
         self.line_offset
     }
 
+    /// The number of columns the region has scrolled over.
+    /// A value of zero represents an unscrolled region.
+    pub fn column_offset(&self) -> usize {
+        self.column_offset
+    }
+
     pub fn scroll_up(&mut self, amount: usize) {
-        self.line_offset = match self.line_offset.checked_sub(amount) {
+        let offset = match self.line_offset.checked_sub(amount) {
             Some(amount) => amount,
             None => 0,
         };
+        self.set_line_offset(offset);
     }
 
     pub fn scroll_down(&mut self, amount: usize) {
-        self.line_offset += amount;
+        self.set_line_offset(self.line_offset + amount);
+    }
+
+    /// Applies a signed offset to the current line offset in a single
+    /// operation, saturating at the top (0) and, when a line count is
+    /// known, at the bottom. Convenient for wheel and trackpad input where
+    /// the delta is naturally signed.
+    pub fn scroll_by(&mut self, delta: isize) {
+        let target = self.line_offset as isize + delta;
+        self.set_line_offset(if target < 0 { 0 } else { target as usize });
+    }
+
+    // Assigns the line offset, clamping it to the document's bounds when a
+    // line count is known. The maximum offset leaves the final line flush
+    // against the bottom of the region (no trailing blank lines), extended
+    // by the configured overscroll allowance. Clamping happens here, at
+    // the single point the offset is written, so every scrolling operation
+    // stays in range.
+    fn set_line_offset(&mut self, offset: usize) {
+        self.line_offset = match self.line_count {
+            Some(line_count) => {
+                let max_offset = line_count.saturating_sub(self.height) + self.overscroll;
+                offset.min(max_offset)
+            },
+            None => offset,
+        };
+    }
+
+    /// Scrolls down a full page (the region's height), mirroring vi's
+    /// `Ctrl-F`. See `ScrollResult` for how the cursor should follow.
+    pub fn scroll_page_down(&mut self) -> ScrollResult {
+        self.scroll_page(self.height as isize)
+    }
+
+    /// Scrolls up a full page (the region's height), mirroring vi's
+    /// `Ctrl-B`.
+    pub fn scroll_page_up(&mut self) -> ScrollResult {
+        self.scroll_page(-(self.height as isize))
+    }
+
+    /// Scrolls down half a page, mirroring vi's `Ctrl-D`.
+    pub fn scroll_half_page_down(&mut self) -> ScrollResult {
+        self.scroll_page((self.height / 2) as isize)
+    }
+
+    /// Scrolls up half a page, mirroring vi's `Ctrl-U`.
+    pub fn scroll_half_page_up(&mut self) -> ScrollResult {
+        self.scroll_page(-((self.height / 2) as isize))
+    }
+
+    // Shared page-scrolling logic: advances the line offset by a signed
+    // delta, clamping at the top of the region and, when a line count is
+    // known, at the bottom, and reports the delta back so the caller can
+    // move the cursor to match.
+    fn scroll_page(&mut self, delta: isize) -> ScrollResult {
+        self.scroll_by(delta);
+
+        ScrollResult {
+            line_offset: self.line_offset,
+            cursor_delta: delta,
+        }
     }
 }
 
-pub fn new(height: usize) -> ScrollableRegion {
-    ScrollableRegion{ height: height, line_offset: 0 }
+pub fn new(height: usize, width: usize) -> ScrollableRegion {
+    ScrollableRegion{
+        height: height,
+        width: width,
+        line_offset: 0,
+        column_offset: 0,
+        line_count: None,
+        overscroll: 0,
+        scroll_margin: 0,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     extern crate scribe;
 
-    use super::{new, ScrollableRegion, Visibility};
+    use super::{new, ScrollableRegion, ScrollAnchor, Visibility};
     use scribe::buffer::line_range;
 
     #[test]
     fn visible_range_works_for_zero_based_line_offsets() {
         let height = 20;
-        let region = new(height);
+        let region = new(height, 80);
         let range = region.visible_range();
         assert_eq!(range.start(), 0);
         assert_eq!(range.end(), height);
@@ -95,15 +265,21 @@ mod tests {
 
     #[test]
     fn visible_range_works_for_non_zero_line_offsets() {
-        let region = ScrollableRegion{ height: 20, line_offset: 10 };
+        let region = ScrollableRegion{ height: 20, width: 80, line_offset: 10, column_offset: 0, line_count: None, overscroll: 0, scroll_margin: 0 };
         let range = region.visible_range();
         assert_eq!(range.start(), 10);
         assert_eq!(range.end(), 30);
     }
 
+    #[test]
+    fn visible_column_range_works_for_non_zero_column_offsets() {
+        let region = ScrollableRegion{ height: 20, width: 80, line_offset: 0, column_offset: 10, line_count: None, overscroll: 0, scroll_margin: 0 };
+        assert_eq!(region.visible_column_range(), 10..90);
+    }
+
     #[test]
     fn scroll_into_view_advances_region_if_line_after_current_range() {
-        let mut region = ScrollableRegion{ height: 20, line_offset: 10 };
+        let mut region = ScrollableRegion{ height: 20, width: 80, line_offset: 10, column_offset: 0, line_count: None, overscroll: 0, scroll_margin: 0 };
         region.scroll_into_view(40);
         let range = region.visible_range();
         assert_eq!(range.start(), 21);
@@ -112,36 +288,63 @@ mod tests {
 
     #[test]
     fn scroll_into_view_recedes_region_if_line_before_current_range() {
-        let mut region = ScrollableRegion{ height: 20, line_offset: 10 };
+        let mut region = ScrollableRegion{ height: 20, width: 80, line_offset: 10, column_offset: 0, line_count: None, overscroll: 0, scroll_margin: 0 };
         region.scroll_into_view(5);
         let range = region.visible_range();
         assert_eq!(range.start(), 5);
         assert_eq!(range.end(), 25);
     }
 
+    #[test]
+    fn scroll_point_into_view_advances_columns_if_point_right_of_range() {
+        let mut region = new(20, 80);
+        region.scroll_point_into_view(5, 100);
+        assert_eq!(region.visible_column_range(), 21..101);
+        assert_eq!(region.line_offset(), 0);
+    }
+
+    #[test]
+    fn scroll_point_into_view_recedes_columns_if_point_left_of_range() {
+        let mut region = ScrollableRegion{ height: 20, width: 80, line_offset: 0, column_offset: 50, line_count: None, overscroll: 0, scroll_margin: 0 };
+        region.scroll_point_into_view(5, 30);
+        assert_eq!(region.visible_column_range(), 30..110);
+    }
+
     #[test]
     fn relative_position_returns_correct_value_when_positive() {
-        let mut region = new(20);
+        let mut region = new(20, 80);
         region.scroll_into_view(30);
-        assert_eq!(region.relative_position(15), Visibility::Visible(4));
+        assert_eq!(region.relative_position(15, 0), Visibility::Visible(4, 0));
     }
 
     #[test]
     fn relative_position_returns_above_region_when_negative() {
-        let mut region = new(20);
+        let mut region = new(20, 80);
         region.scroll_into_view(30);
-        assert_eq!(region.relative_position(0), Visibility::AboveRegion);
+        assert_eq!(region.relative_position(0, 0), Visibility::AboveRegion);
     }
 
     #[test]
     fn relative_position_returns_below_region_when_beyond_visible_range() {
-        let region = new(20);
-        assert_eq!(region.relative_position(20), Visibility::BelowRegion);
+        let region = new(20, 80);
+        assert_eq!(region.relative_position(20, 0), Visibility::BelowRegion);
+    }
+
+    #[test]
+    fn relative_position_returns_left_of_region_when_before_column_range() {
+        let region = ScrollableRegion{ height: 20, width: 80, line_offset: 0, column_offset: 10, line_count: None, overscroll: 0, scroll_margin: 0 };
+        assert_eq!(region.relative_position(0, 5), Visibility::LeftOfRegion);
+    }
+
+    #[test]
+    fn relative_position_returns_right_of_region_when_beyond_column_range() {
+        let region = new(20, 80);
+        assert_eq!(region.relative_position(0, 80), Visibility::RightOfRegion);
     }
 
     #[test]
     fn scroll_down_increases_line_offset_by_amount() {
-        let mut region = new(20);
+        let mut region = new(20, 80);
         region.scroll_down(10);
         assert_eq!(
             region.visible_range(),
@@ -151,7 +354,7 @@ mod tests {
 
     #[test]
     fn scroll_up_decreases_line_offset_by_amount() {
-        let mut region = new(20);
+        let mut region = new(20, 80);
         region.scroll_down(10);
         region.scroll_up(5);
         assert_eq!(
@@ -160,13 +363,164 @@ mod tests {
         );
     }
 
+    #[test]
+    fn scroll_page_down_advances_offset_by_height() {
+        let mut region = new(20, 80);
+        let result = region.scroll_page_down();
+        assert_eq!(region.line_offset(), 20);
+        assert_eq!(result.line_offset, 20);
+        assert_eq!(result.cursor_delta, 20);
+    }
+
+    #[test]
+    fn scroll_page_up_recedes_offset_by_height() {
+        let mut region = ScrollableRegion{ height: 20, width: 80, line_offset: 50, column_offset: 0, line_count: None, overscroll: 0, scroll_margin: 0 };
+        let result = region.scroll_page_up();
+        assert_eq!(region.line_offset(), 30);
+        assert_eq!(result.cursor_delta, -20);
+    }
+
+    #[test]
+    fn scroll_half_page_down_advances_offset_by_half_height() {
+        let mut region = new(20, 80);
+        let result = region.scroll_half_page_down();
+        assert_eq!(region.line_offset(), 10);
+        assert_eq!(result.cursor_delta, 10);
+    }
+
+    #[test]
+    fn scroll_page_up_clamps_at_top_but_reports_full_delta() {
+        let mut region = ScrollableRegion{ height: 20, width: 80, line_offset: 5, column_offset: 0, line_count: None, overscroll: 0, scroll_margin: 0 };
+        let result = region.scroll_page_up();
+        assert_eq!(region.line_offset(), 0);
+        assert_eq!(result.line_offset, 0);
+        assert_eq!(result.cursor_delta, -20);
+    }
+
     #[test]
     fn scroll_up_does_not_scroll_beyond_top_of_region() {
-        let mut region = new(20);
+        let mut region = new(20, 80);
         region.scroll_up(5);
         assert_eq!(
             region.visible_range(),
             line_range::new(0, 20)
         );
     }
+
+    #[test]
+    fn scroll_to_center_places_line_in_the_middle_of_the_region() {
+        let mut region = new(20, 80);
+        region.scroll_to(30, ScrollAnchor::Center);
+        assert_eq!(region.line_offset(), 20);
+    }
+
+    #[test]
+    fn scroll_to_top_places_line_at_the_top_of_the_region() {
+        let mut region = new(20, 80);
+        region.scroll_to(30, ScrollAnchor::Top);
+        assert_eq!(region.line_offset(), 30);
+    }
+
+    #[test]
+    fn scroll_to_bottom_places_line_at_the_bottom_of_the_region() {
+        let mut region = new(20, 80);
+        region.scroll_to(30, ScrollAnchor::Bottom);
+        assert_eq!(region.line_offset(), 11);
+    }
+
+    #[test]
+    fn scroll_to_clamps_against_the_top_of_the_document() {
+        let mut region = new(20, 80);
+        region.scroll_to(5, ScrollAnchor::Center);
+        assert_eq!(region.line_offset(), 0);
+    }
+
+    #[test]
+    fn scroll_by_applies_a_positive_delta() {
+        let mut region = new(20, 80);
+        region.scroll_by(10);
+        assert_eq!(region.line_offset(), 10);
+    }
+
+    #[test]
+    fn scroll_by_saturates_at_the_top_for_negative_deltas() {
+        let mut region = new(20, 80);
+        region.scroll_down(5);
+        region.scroll_by(-20);
+        assert_eq!(region.line_offset(), 0);
+    }
+
+    #[test]
+    fn scroll_by_clamps_at_the_bottom_when_line_count_known() {
+        let mut region = new(20, 80);
+        region.set_line_count(30);
+        region.scroll_by(100);
+        assert_eq!(region.line_offset(), 10);
+    }
+
+    #[test]
+    fn scroll_down_clamps_offset_to_document_bounds() {
+        let mut region = new(20, 80);
+        region.set_line_count(30);
+        region.scroll_down(100);
+        assert_eq!(region.line_offset(), 10);
+    }
+
+    #[test]
+    fn scroll_down_does_not_underflow_when_line_count_below_height() {
+        let mut region = new(20, 80);
+        region.set_line_count(5);
+        region.scroll_down(100);
+        assert_eq!(region.line_offset(), 0);
+    }
+
+    #[test]
+    fn set_line_count_resanitizes_the_current_offset() {
+        let mut region = new(20, 80);
+        region.scroll_down(100);
+        region.set_line_count(30);
+        assert_eq!(region.line_offset(), 10);
+    }
+
+    #[test]
+    fn overscroll_allowance_extends_the_maximum_offset() {
+        let mut region = new(20, 80);
+        region.set_line_count(30);
+        region.set_overscroll(5);
+        region.scroll_down(100);
+        assert_eq!(region.line_offset(), 15);
+    }
+
+    #[test]
+    fn scroll_into_view_keeps_margin_above_target_near_top() {
+        let mut region = ScrollableRegion{
+            height: 20, width: 80, line_offset: 10, column_offset: 0,
+            line_count: None, overscroll: 0, scroll_margin: 3,
+        };
+        region.scroll_into_view(11);
+        assert_eq!(region.line_offset(), 8);
+    }
+
+    #[test]
+    fn scroll_into_view_keeps_margin_below_target_near_bottom() {
+        let mut region = new(20, 80);
+        region.set_scroll_margin(3);
+        region.scroll_into_view(25);
+        assert_eq!(region.line_offset(), 9);
+    }
+
+    #[test]
+    fn scroll_into_view_margin_does_not_scroll_below_top_of_document() {
+        let mut region = new(20, 80);
+        region.set_scroll_margin(3);
+        region.scroll_into_view(1);
+        assert_eq!(region.line_offset(), 0);
+    }
+
+    #[test]
+    fn scroll_into_view_with_zero_margin_preserves_minimal_movement() {
+        let mut region = new(20, 80);
+        region.scroll_into_view(25);
+        assert_eq!(region.line_offset(), 6);
+    }
 }